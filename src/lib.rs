@@ -8,14 +8,62 @@
 
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::num::NonZeroUsize;
+
 pub use bytes::Bytes;
 pub use bytestring::ByteString;
 
-use bytes::BytesMut;
+use bytes::buf::UninitSlice;
+use bytes::{BufMut, BytesMut};
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, Default)]
 pub struct BytesPool {
     inner: BytesMut,
+    /// Whether a non-empty split has happened since `inner` was last known to start at the
+    /// beginning of its backing allocation. `BytesMut` has no public way to ask "is there any
+    /// dead space behind the current window," so `try_reclaim_all` uses this alongside a probe
+    /// reservation to tell "nothing to reclaim" apart from "still shared" — both present
+    /// identically to a caller of `BytesMut::try_reclaim`.
+    dead_space: bool,
+}
+
+// `dead_space` is bookkeeping for `try_reclaim_all`, not part of the pool's logical content, so
+// equality and hashing are derived from `inner` alone, matching how `BytesMut`/`Vec` compare by
+// content rather than by spare capacity.
+impl PartialEq for BytesPool {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl Eq for BytesPool {}
+
+impl PartialOrd for BytesPool {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BytesPool {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl Hash for BytesPool {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
 }
 
 impl BytesPool {
@@ -35,6 +83,7 @@ impl BytesPool {
     pub fn new() -> Self {
         Self {
             inner: BytesMut::new(),
+            dead_space: false,
         }
     }
 
@@ -54,6 +103,7 @@ impl BytesPool {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             inner: BytesMut::with_capacity(capacity),
+            dead_space: false,
         }
     }
 
@@ -91,7 +141,7 @@ impl BytesPool {
     #[inline]
     pub fn share_bytes(&mut self, bytes: &[u8]) -> Bytes {
         self.inner.extend_from_slice(bytes);
-        self.inner.split().freeze()
+        self.finish_share()
     }
 
     /// Creates an immutable string that can be shared across threads and cheaply cloned.
@@ -117,6 +167,75 @@ impl BytesPool {
         unsafe { ByteString::from_bytes_unchecked(bytes) }
     }
 
+    /// Creates an immutable slice of bytes from multiple fragments, as though they were
+    /// concatenated, that can be shared across threads and cheaply cloned.
+    ///
+    /// The total length of `chunks` is reserved once up front, so the buffer is resized at most
+    /// once, rather than possibly once per fragment as calling
+    /// [`share_bytes`](BytesPool::share_bytes) in a loop could. Each fragment is still appended
+    /// with its own (non-resizing) capacity check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes_pool::BytesPool;
+    ///
+    /// let mut pool = BytesPool::with_capacity(64);
+    ///
+    /// let bytes = pool.share_chunks(&[b"hello", b" ", b"world"]);
+    ///
+    /// assert_eq!(bytes, &b"hello world"[..]);
+    /// ```
+    pub fn share_chunks(&mut self, chunks: &[&[u8]]) -> Bytes {
+        let total_len = chunks.iter().map(|chunk| chunk.len()).sum();
+        self.inner.reserve(total_len);
+        for chunk in chunks {
+            self.inner.extend_from_slice(chunk);
+        }
+        self.finish_share()
+    }
+
+    /// Creates an immutable string from multiple fragments, as though they were concatenated,
+    /// that can be shared across threads and cheaply cloned.
+    ///
+    /// The total length of `strs` is reserved once up front, so the buffer is resized at most
+    /// once, rather than possibly once per fragment as calling
+    /// [`share_str`](BytesPool::share_str) in a loop could. Each fragment is still appended with
+    /// its own (non-resizing) capacity check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes_pool::BytesPool;
+    ///
+    /// let mut pool = BytesPool::with_capacity(64);
+    ///
+    /// let s = pool.share_concat_str(&["hello", " ", "world"]);
+    ///
+    /// assert_eq!(s, "hello world");
+    /// ```
+    pub fn share_concat_str(&mut self, strs: &[&str]) -> ByteString {
+        let total_len = strs.iter().map(|s| s.len()).sum();
+        self.inner.reserve(total_len);
+        for s in strs {
+            self.inner.extend_from_slice(s.as_bytes());
+        }
+        let bytes = self.finish_share();
+        // SAFETY: `self.inner` contains only valid UTF-8.
+        unsafe { ByteString::from_bytes_unchecked(bytes) }
+    }
+
+    /// Splits off everything written so far as a shared `Bytes`, updating `dead_space`
+    /// bookkeeping for `try_reclaim_all` if the split was non-empty.
+    #[inline]
+    fn finish_share(&mut self) -> Bytes {
+        let shared = self.inner.split().freeze();
+        if !shared.is_empty() {
+            self.dead_space = true;
+        }
+        shared
+    }
+
     /// Reserves capacity for at least `additional` bytes to be inserted
     /// into the given `BytesPool`.
     ///
@@ -145,7 +264,58 @@ impl BytesPool {
     /// Panics if the new capacity overflows `usize`.
     #[inline]
     pub fn reserve(&mut self, additional: usize) {
+        self.sync_dead_space();
+        let was_shared = self.inner.is_empty() && self.dead_space;
+        let rem = self.inner.capacity() - self.inner.len();
+        self.inner.reserve(additional);
+        if was_shared && additional > rem {
+            // Unlike `try_reclaim`, `reserve` is allowed to allocate, so if growing the buffer
+            // still needed to happen while it was confirmed shared (the `sync_dead_space` call
+            // above didn't resolve it), it necessarily just discarded that shared buffer for a
+            // fresh, unshared one rather than leaving any of it behind.
+            self.dead_space = false;
+        }
+    }
+
+    /// Reserves capacity for at least `additional` bytes to be inserted into the given
+    /// `BytesPool`, returning an error instead of panicking if the new capacity would overflow
+    /// `usize`.
+    ///
+    /// This mirrors [`reserve`](BytesPool::reserve), which is preferable whenever the caller
+    /// does not need to handle a reservation failure gracefully (for example, a server that
+    /// should degrade rather than abort if it cannot grow a shared buffer).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `additional` added to the current length would overflow `usize`. This
+    /// does not guard against allocator failure: the underlying [`BytesMut::reserve`] call
+    /// still aborts the process on allocation failure, since the `bytes` crate does not expose a
+    /// fallible allocation path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes_pool::BytesPool;
+    ///
+    /// let mut pool = BytesPool::with_capacity(128);
+    ///
+    /// assert!(pool.try_reserve(128).is_ok());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if self.inner.len().checked_add(additional).is_none() {
+            return Err(TryReserveError { _private: () });
+        }
+        self.sync_dead_space();
+        let was_shared = self.inner.is_empty() && self.dead_space;
+        let rem = self.inner.capacity() - self.inner.len();
         self.inner.reserve(additional);
+        if was_shared && additional > rem {
+            // See the matching comment in `reserve`: growing a confirmed-shared buffer here
+            // always means it was just discarded for a fresh, unshared one.
+            self.dead_space = false;
+        }
+        Ok(())
     }
 
     /// Attempts to cheaply reclaim already allocated capacity for at least `additional` more
@@ -185,6 +355,234 @@ impl BytesPool {
     #[inline]
     #[must_use = "consider BytesPool::reserve if you need an infallible reservation"]
     pub fn try_reclaim(&mut self, additional: usize) -> bool {
+        // Resolve any reclaimable dead space first: `try_reclaim` never allocates, so unlike
+        // `reserve` it can't silently discard a still-shared buffer out from under `dead_space`
+        // — but it can still quietly rewind a unique one on its own, which would otherwise look
+        // identical to "still shared" to a probe run only afterward.
+        self.sync_dead_space();
         self.inner.try_reclaim(additional)
     }
+
+    /// Attempts to reclaim the entire backing allocation, rewinding the pool back to the start
+    /// of its buffer, and returns the reclaimed capacity.
+    ///
+    /// Unlike [`try_reclaim`](BytesPool::try_reclaim), this does not require knowing how much
+    /// capacity is needed up front: it succeeds whenever the pool is empty and nothing returned
+    /// by [`share_bytes`](BytesPool::share_bytes) or [`share_str`](BytesPool::share_str) still
+    /// holds a reference into the buffer, which is the common case for a pool that is drained
+    /// between batches. Returns `None` if the buffer is non-empty, was never allocated, or is
+    /// still shared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes_pool::BytesPool;
+    ///
+    /// let mut pool = BytesPool::with_capacity(64);
+    ///
+    /// // Nothing has been shared yet, so the pool already spans its whole allocation.
+    /// assert_eq!(Some(64), pool.try_reclaim_all().map(|cap| cap.get()));
+    ///
+    /// let bytes = pool.share_bytes(b"abcd");
+    /// assert_eq!(60, pool.capacity());
+    ///
+    /// // Still shared, so the allocation can't be reclaimed yet.
+    /// assert_eq!(None, pool.try_reclaim_all());
+    ///
+    /// drop(bytes);
+    ///
+    /// assert_eq!(Some(64), pool.try_reclaim_all().map(|cap| cap.get()));
+    ///
+    /// // Calling it again when there's nothing left to reclaim is a no-op success, not a
+    /// // regression back to `None`.
+    /// assert_eq!(Some(64), pool.try_reclaim_all().map(|cap| cap.get()));
+    /// ```
+    #[must_use = "consider BytesPool::reserve if you need an infallible reservation"]
+    pub fn try_reclaim_all(&mut self) -> Option<NonZeroUsize> {
+        if !self.inner.is_empty() {
+            return None;
+        }
+        self.sync_dead_space();
+        if self.dead_space {
+            return None;
+        }
+        NonZeroUsize::new(self.inner.capacity())
+    }
+
+    /// Opportunistically reclaims any dead space behind the window and clears `dead_space` to
+    /// match, but only when that can be established for certain. Requesting more than the
+    /// pool's currently visible capacity forces `BytesMut` to either rewind to the start of the
+    /// full allocation or fail outright, rather than granting exactly the amount requested — and
+    /// a successful rewind always means real dead space just got reclaimed, so it's safe to
+    /// clear the flag. A failed probe is left alone instead of being read as "still shared":
+    /// it fires just as easily when there was never any dead space to begin with, so on its own
+    /// it can't tell those two apart. Callers that can resolve that ambiguity some other way
+    /// (`try_reclaim_all`, and the shared-buffer discard that `reserve`/`try_reserve` account for
+    /// separately) do so themselves; this only ever moves `dead_space` from `true` to `false`,
+    /// never the other way, so calling it speculatively is always safe.
+    ///
+    /// Only meaningful while `self.inner` is empty — the same condition `try_reclaim_all`
+    /// requires — so this is a no-op otherwise.
+    fn sync_dead_space(&mut self) {
+        if !self.inner.is_empty() {
+            return;
+        }
+        if let Some(requested) = self.inner.capacity().checked_add(1) {
+            if self.inner.try_reclaim(requested) {
+                self.dead_space = false;
+            }
+        }
+    }
+
+    /// Returns a builder for incrementally writing a shared slice or string into this pool.
+    ///
+    /// Unlike [`share_bytes`](BytesPool::share_bytes) and [`share_str`](BytesPool::share_str),
+    /// which take an already-materialized `&[u8]`/`&str`, the returned [`PoolBuilder`] implements
+    /// [`fmt::Write`] and [`BufMut`] so that formatted or composed data can be written directly
+    /// into the pool's buffer, without needing an intermediate `String`/`Vec` to assemble it
+    /// first.
+    ///
+    /// If the builder is dropped without calling [`finish`](PoolBuilder::finish) or
+    /// [`finish_str`](PoolBuilder::finish_str) — for example because a fallible writer errored
+    /// out partway through — whatever was written is discarded rather than being silently
+    /// prepended to the pool's next shared value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    ///
+    /// use bytes_pool::BytesPool;
+    ///
+    /// let mut pool = BytesPool::with_capacity(64);
+    ///
+    /// let mut builder = pool.builder();
+    /// write!(builder, "{} + {} = {}", 2, 2, 4).unwrap();
+    /// let s = builder.finish_str();
+    ///
+    /// assert_eq!(s, "2 + 2 = 4");
+    /// ```
+    #[inline]
+    pub fn builder(&mut self) -> PoolBuilder<'_> {
+        let start = self.inner.len();
+        PoolBuilder { pool: self, start }
+    }
+}
+
+/// A handle for incrementally writing into a [`BytesPool`]'s internal buffer.
+///
+/// Created by [`BytesPool::builder`]. Writes go directly into the pool's buffer through
+/// [`fmt::Write`] or [`BufMut`], and [`finish`](PoolBuilder::finish) /
+/// [`finish_str`](PoolBuilder::finish_str) split off what's been written as a shared value, the
+/// same way [`share_bytes`](BytesPool::share_bytes) does.
+///
+/// Dropping a `PoolBuilder` without finishing it discards whatever was written instead of
+/// leaving it to contaminate the pool's next shared value.
+#[derive(Debug)]
+pub struct PoolBuilder<'a> {
+    pool: &'a mut BytesPool,
+    /// The pool's buffer length when this builder was created, i.e. before any of this
+    /// builder's writes. Used by `Drop` to discard a partial write.
+    start: usize,
 }
+
+impl PoolBuilder<'_> {
+    /// Finishes building and returns the written bytes as a shared, cheaply-cloned [`Bytes`].
+    #[inline]
+    pub fn finish(self) -> Bytes {
+        self.pool.finish_share()
+    }
+
+    /// Finishes building and returns the written bytes as a shared [`ByteString`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the written bytes are not valid UTF-8. This can happen if the [`BufMut`]
+    /// implementation was used to write something other than valid UTF-8, since unlike
+    /// [`fmt::Write`] it does not require the written bytes to form a valid `str`.
+    #[inline]
+    pub fn finish_str(self) -> ByteString {
+        let bytes = self.finish();
+        assert!(
+            core::str::from_utf8(&bytes).is_ok(),
+            "PoolBuilder::finish_str: written bytes are not valid UTF-8"
+        );
+        // SAFETY: just verified that `bytes` is valid UTF-8.
+        unsafe { ByteString::from_bytes_unchecked(bytes) }
+    }
+}
+
+impl fmt::Write for PoolBuilder<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.pool.inner.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+// SAFETY: `chunk_mut` and `advance_mut` delegate directly to `BytesMut`'s implementation.
+unsafe impl BufMut for PoolBuilder<'_> {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.pool.inner.remaining_mut()
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        // SAFETY: the caller upholds `BufMut::advance_mut`'s safety invariants.
+        unsafe { self.pool.inner.advance_mut(cnt) }
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.pool.inner.chunk_mut()
+    }
+}
+
+impl Drop for PoolBuilder<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // `finish`/`finish_str` already split off everything up to the current length, leaving
+        // nothing after `self.start` to truncate; this only has an effect for a partial write
+        // that was never finished.
+        self.pool.inner.truncate(self.start);
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for PoolBuilder<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pool.inner.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.pool.inner.extend_from_slice(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The error returned by [`BytesPool::try_reserve`] when the requested capacity would overflow
+/// `usize`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryReserveError {
+    _private: (),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("capacity overflow while reserving pool memory")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}